@@ -0,0 +1,25 @@
+//! A small stand-in for the `equivalent` crate's `Equivalent` trait.
+//!
+//! It lets a lookup be keyed by a borrowed type (e.g. `&str` against a `HashSet<String>`)
+//! without requiring the borrowed type's `Eq` to exactly agree with `Borrow`'s.
+
+/// Key equivalence trait.
+///
+/// This trait lets a collection be probed with a type other than the one it stores,
+/// similar to [`Borrow`](core::borrow::Borrow) but without the stricter requirement
+/// that both types agree on `Eq` and `Ord`. Lookups still hash the probe with its own
+/// `Hash`, so that must agree with the stored type's.
+pub trait Equivalent<K: ?Sized> {
+    /// Checks if this value is equivalent to the given key.
+    fn equivalent(&self, key: &K) -> bool;
+}
+
+impl<Q: ?Sized, K: ?Sized> Equivalent<K> for Q
+where
+    Q: Eq,
+    K: core::borrow::Borrow<Q>,
+{
+    fn equivalent(&self, key: &K) -> bool {
+        *self == *key.borrow()
+    }
+}
@@ -0,0 +1,35 @@
+//! A Rust port of Google's SwissTable hash map, extended with a hybrid
+//! linear-scan/hashed storage strategy for small collections.
+
+extern crate alloc;
+
+mod equivalent;
+pub mod map;
+pub mod raw;
+pub mod rustc_entry;
+mod rustc_set_entry;
+pub mod set;
+
+pub use crate::equivalent::Equivalent;
+pub use crate::map::HashMap;
+pub use crate::set::HashSet;
+
+/// Re-exports of the `HashSet`-adjacent Rustc-style entry API, matching the module
+/// layout callers of [`HashSet`] expect these entry types under.
+pub mod hash_set {
+    pub use crate::rustc_set_entry::{
+        RustcEntry, RustcEntryRef, RustcOccupiedEntry, RustcOccupiedEntryRef, RustcVacantEntry,
+        RustcVacantEntryRef,
+    };
+    pub use crate::set::HashSet;
+}
+
+/// Re-exports of the `HashMap`-adjacent Rustc-style entry API, matching the module
+/// layout callers of [`HashMap`] expect these entry types under.
+pub mod hash_map {
+    pub use crate::map::HashMap;
+    pub use crate::rustc_entry::{
+        RustcEntry, RustcEntryRef, RustcOccupiedEntry, RustcOccupiedEntryRef, RustcVacantEntry,
+        RustcVacantEntryRef,
+    };
+}
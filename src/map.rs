@@ -0,0 +1,169 @@
+//! A hash map implemented with a hybrid linear-scan/hashed [`RawTable`].
+
+use crate::raw::{Allocator, Global, RawLocation, RawTable};
+use crate::rustc_entry::{
+    RustcEntry, RustcEntryRef, RustcOccupiedEntry, RustcOccupiedEntryRef, RustcVacantEntry,
+    RustcVacantEntryRef,
+};
+use crate::Equivalent;
+use alloc::borrow::ToOwned;
+use core::hash::{BuildHasher, Hash};
+use std::collections::hash_map::RandomState;
+
+pub(crate) fn make_hash<Q, S>(hash_builder: &S, val: &Q) -> u64
+where
+    Q: Hash + ?Sized,
+    S: BuildHasher,
+{
+    hash_builder.hash_one(val)
+}
+
+pub(crate) fn find<K, V, Q, A>(
+    table: &RawTable<(K, V), A>,
+    hash: u64,
+    key: &Q,
+) -> Option<RawLocation>
+where
+    Q: Equivalent<K> + ?Sized,
+    A: Allocator + Clone,
+{
+    table.find(hash, |(k, _)| key.equivalent(k))
+}
+
+/// A hash map, mirroring [`std::collections::HashMap`]'s interface but backed by a
+/// [`RawTable`] that starts out as a flat linear-scan array for small sizes.
+pub struct HashMap<K, V, S = RandomState, A: Allocator + Clone = Global> {
+    pub(crate) hash_builder: S,
+    pub(crate) table: RawTable<(K, V), A>,
+}
+
+impl<K, V> HashMap<K, V, RandomState, Global> {
+    /// Creates an empty `HashMap`.
+    pub fn new() -> Self {
+        Self::with_hasher(RandomState::new())
+    }
+}
+
+impl<K, V> Default for HashMap<K, V, RandomState, Global> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V, S, A: Allocator + Clone> HashMap<K, V, S, A> {
+    /// Creates an empty `HashMap` which will use `hash_builder` to hash keys.
+    pub fn with_hasher(hash_builder: S) -> Self {
+        Self::with_hasher_and_array_threshold(hash_builder, crate::raw::DEFAULT_ARRAY_THRESHOLD)
+    }
+
+    /// Creates an empty `HashMap` that stores up to `array_threshold` entries in a
+    /// flat linear-scan array before migrating to a hashed representation.
+    pub fn with_hasher_and_array_threshold(hash_builder: S, array_threshold: usize) -> Self {
+        HashMap {
+            hash_builder,
+            table: RawTable::with_array_threshold(array_threshold),
+        }
+    }
+
+    /// The number of elements in the map.
+    pub fn len(&self) -> usize {
+        self.table.len()
+    }
+
+    /// Whether the map contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.table.len() == 0
+    }
+
+    /// A reference to the map's [`BuildHasher`].
+    pub fn hasher(&self) -> &S {
+        &self.hash_builder
+    }
+}
+
+impl<K: Eq + Hash, V, S: BuildHasher, A: Allocator + Clone> HashMap<K, V, S, A> {
+    /// Inserts a key-value pair, returning the previous value if the key was present.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        match self.rustc_entry(key) {
+            RustcEntry::Occupied(mut entry) => Some(entry.insert(value)),
+            RustcEntry::Vacant(entry) => {
+                entry.insert(value);
+                None
+            }
+        }
+    }
+
+    /// Gets a reference to the value associated with `key`, if present.
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        Q: Equivalent<K> + Hash + ?Sized,
+    {
+        let hash = make_hash(&self.hash_builder, key);
+        find(&self.table, hash, key).map(move |location| &self.table.get(&location).1)
+    }
+
+    /// Whether `key` is present in the map.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        Q: Equivalent<K> + Hash + ?Sized,
+    {
+        self.get(key).is_some()
+    }
+
+    /// Removes `key`, returning its value if it was present.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        Q: Equivalent<K> + Hash + ?Sized,
+    {
+        let hash = make_hash(&self.hash_builder, key);
+        let location = find(&self.table, hash, key)?;
+        Some(self.table.remove(location).1)
+    }
+
+    /// Gets the given key's corresponding entry in the map for in-place manipulation.
+    pub fn rustc_entry(&mut self, key: K) -> RustcEntry<'_, K, V, S, A> {
+        let hash = make_hash(&self.hash_builder, &key);
+        self.rustc_entry_with_hash(key, hash)
+    }
+
+    /// Gets the given key's corresponding entry in the map for in-place manipulation,
+    /// using an already computed hash instead of hashing `key` again.
+    pub fn rustc_entry_with_hash(&mut self, key: K, hash: u64) -> RustcEntry<'_, K, V, S, A> {
+        match find(&self.table, hash, &key) {
+            Some(location) => RustcEntry::Occupied(RustcOccupiedEntry::new(
+                Some(key),
+                location,
+                &mut self.table,
+            )),
+            None => RustcEntry::Vacant(RustcVacantEntry::new(
+                key,
+                hash,
+                &self.hash_builder,
+                &mut self.table,
+            )),
+        }
+    }
+
+    /// Gets the given key's corresponding entry in the map for in-place manipulation,
+    /// without requiring ownership of `key` unless a new entry ends up being inserted.
+    pub fn rustc_entry_ref<'a, 'b, Q>(
+        &'a mut self,
+        key: &'b Q,
+    ) -> RustcEntryRef<'a, 'b, K, Q, V, S, A>
+    where
+        Q: Equivalent<K> + Hash + ?Sized + ToOwned<Owned = K>,
+    {
+        let hash = make_hash(&self.hash_builder, key);
+        match find(&self.table, hash, key) {
+            Some(location) => {
+                RustcEntryRef::Occupied(RustcOccupiedEntryRef::new(location, &mut self.table))
+            }
+            None => RustcEntryRef::Vacant(RustcVacantEntryRef::new(
+                key,
+                hash,
+                &self.hash_builder,
+                &mut self.table,
+            )),
+        }
+    }
+}
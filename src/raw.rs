@@ -0,0 +1,419 @@
+//! The low-level storage backing [`HashMap`](crate::HashMap) and
+//! [`HashSet`](crate::HashSet).
+//!
+//! [`RawTable`] starts out as a flat array with linear-scan lookups, which avoids
+//! hashing entirely and is cheap to probe for the small sizes most collections in
+//! practice never grow past. When an insert would take it past a
+//! [`RawTable::with_array_threshold`]-configured number of entries, it lazily
+//! migrates to an open-addressed hashed table as part of that insert, so the array
+//! never holds more than the threshold. The migration never happens in reverse: once a table
+//! has switched to the hashed representation, removals do not shrink it back to the
+//! array, since flipping back and forth between representations near the threshold
+//! would thrash.
+
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+use core::mem;
+
+/// An allocator that can be used to back a [`RawTable`].
+///
+/// This mirrors the allocator plumbing of the real crate; it exists so that
+/// [`HashMap`](crate::HashMap)/[`HashSet`](crate::HashSet) and their entry APIs can be
+/// generic over an allocator parameter, even though [`Global`] is the only allocator
+/// implemented here.
+pub trait Allocator {}
+
+/// The global allocator.
+#[derive(Copy, Clone, Default, Debug)]
+pub struct Global;
+
+impl Allocator for Global {}
+
+/// The default number of entries a [`RawTable`] keeps in its flat array before
+/// migrating to the hashed representation.
+pub const DEFAULT_ARRAY_THRESHOLD: usize = 32;
+
+enum Slot<T> {
+    Empty,
+    Tombstone,
+    Full(u64, T),
+}
+
+struct HashedStorage<T> {
+    slots: Vec<Slot<T>>,
+    len: usize,
+}
+
+impl<T> HashedStorage<T> {
+    fn with_capacity(min_capacity: usize) -> Self {
+        // Keep the load factor under 3/4 even immediately after construction.
+        let capacity = ((min_capacity * 4 / 3) + 1).next_power_of_two().max(8);
+        let mut slots = Vec::with_capacity(capacity);
+        slots.resize_with(capacity, || Slot::Empty);
+        HashedStorage { slots, len: 0 }
+    }
+
+    fn mask(&self) -> usize {
+        self.slots.len() - 1
+    }
+
+    fn find(&self, hash: u64, mut eq: impl FnMut(&T) -> bool) -> Option<usize> {
+        if self.slots.is_empty() {
+            return None;
+        }
+        let mask = self.mask();
+        let mut index = hash as usize & mask;
+        for _ in 0..self.slots.len() {
+            match &self.slots[index] {
+                Slot::Full(h, item) if *h == hash && eq(item) => return Some(index),
+                Slot::Empty => return None,
+                _ => {}
+            }
+            index = (index + 1) & mask;
+        }
+        None
+    }
+
+    /// Finds the slot a value with the given hash should be inserted into,
+    /// growing the table first if it's getting too full.
+    fn prepare_insert_slot(&mut self, hash: u64) -> usize {
+        if self.slots.is_empty() || (self.len + 1) * 4 >= self.slots.len() * 3 {
+            self.grow();
+        }
+        let mask = self.mask();
+        let mut index = hash as usize & mask;
+        loop {
+            match &self.slots[index] {
+                Slot::Empty | Slot::Tombstone => return index,
+                Slot::Full(..) => index = (index + 1) & mask,
+            }
+        }
+    }
+
+    fn grow(&mut self) {
+        let new_capacity = (self.slots.len() * 2).max(8);
+        let old_slots = mem::replace(&mut self.slots, {
+            let mut v = Vec::with_capacity(new_capacity);
+            v.resize_with(new_capacity, || Slot::Empty);
+            v
+        });
+        self.len = 0;
+        for slot in old_slots {
+            if let Slot::Full(hash, item) = slot {
+                let index = self.prepare_insert_slot(hash);
+                self.slots[index] = Slot::Full(hash, item);
+                self.len += 1;
+            }
+        }
+    }
+
+    fn insert_at(&mut self, index: usize, hash: u64, value: T) {
+        self.slots[index] = Slot::Full(hash, value);
+        self.len += 1;
+    }
+
+    fn get(&self, index: usize) -> &T {
+        match &self.slots[index] {
+            Slot::Full(_, item) => item,
+            _ => unreachable!("stale RawLocation"),
+        }
+    }
+
+    fn get_mut(&mut self, index: usize) -> &mut T {
+        match &mut self.slots[index] {
+            Slot::Full(_, item) => item,
+            _ => unreachable!("stale RawLocation"),
+        }
+    }
+
+    fn remove(&mut self, index: usize) -> T {
+        match mem::replace(&mut self.slots[index], Slot::Tombstone) {
+            Slot::Full(_, item) => {
+                self.len -= 1;
+                item
+            }
+            _ => unreachable!("stale RawLocation"),
+        }
+    }
+}
+
+enum Repr<T> {
+    Array(Vec<T>),
+    Table(HashedStorage<T>),
+}
+
+/// The result of a successful [`RawTable::find`]: a location that is guaranteed to
+/// stay valid until the next call to [`RawTable::prepare_insert`] or
+/// [`RawTable::remove`].
+pub struct RawLocation(RawLocationInner);
+
+enum RawLocationInner {
+    Array(usize),
+    Table(usize),
+}
+
+/// A location reserved by [`RawTable::prepare_insert`] to place a new value into,
+/// obtained before the value itself is available (e.g. while it is still borrowed).
+pub struct RawInsertSlot(RawInsertSlotInner);
+
+enum RawInsertSlotInner {
+    Array,
+    Table(usize),
+}
+
+/// The hybrid linear-scan/hashed storage backing [`HashMap`](crate::HashMap) and
+/// [`HashSet`](crate::HashSet).
+pub struct RawTable<T, A: Allocator + Clone = Global> {
+    repr: Repr<T>,
+    array_threshold: usize,
+    _alloc: PhantomData<A>,
+}
+
+impl<T, A: Allocator + Clone> RawTable<T, A> {
+    /// Creates an empty `RawTable`, using [`DEFAULT_ARRAY_THRESHOLD`] as the
+    /// array-to-table migration threshold.
+    pub fn new() -> Self {
+        Self::with_array_threshold(DEFAULT_ARRAY_THRESHOLD)
+    }
+
+    /// Creates an empty `RawTable` that stays in the flat-array representation for up
+    /// to `array_threshold` entries before migrating to a hashed table.
+    pub fn with_array_threshold(array_threshold: usize) -> Self {
+        RawTable {
+            repr: Repr::Array(Vec::new()),
+            array_threshold,
+            _alloc: PhantomData,
+        }
+    }
+
+    /// The number of entries currently stored.
+    pub fn len(&self) -> usize {
+        match &self.repr {
+            Repr::Array(v) => v.len(),
+            Repr::Table(t) => t.len,
+        }
+    }
+
+    /// Whether this table holds no entries.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Whether this table is still using the flat-array representation.
+    pub fn is_array(&self) -> bool {
+        matches!(self.repr, Repr::Array(_))
+    }
+
+    /// Looks up a value by hash and equality predicate.
+    ///
+    /// In the array representation this scans linearly and never consults `hash`;
+    /// in the table representation `hash` is used to narrow the probe sequence.
+    pub fn find(&self, hash: u64, mut eq: impl FnMut(&T) -> bool) -> Option<RawLocation> {
+        match &self.repr {
+            Repr::Array(v) => v
+                .iter()
+                .position(&mut eq)
+                .map(|i| RawLocation(RawLocationInner::Array(i))),
+            Repr::Table(t) => t
+                .find(hash, eq)
+                .map(|i| RawLocation(RawLocationInner::Table(i))),
+        }
+    }
+
+    /// Reserves a slot to insert a value with the given hash into, migrating from
+    /// the array representation to the hashed one first if this insertion would
+    /// cross `array_threshold`. `hash_of` is used to rehash the entries already in
+    /// the array during that migration; it is never called in the table
+    /// representation, since those entries already carry their hash.
+    ///
+    /// The returned [`RawInsertSlot`] remains valid across the migration it may
+    /// itself have triggered, so a [`RustcVacantEntry`](crate::rustc_entry::RustcVacantEntry)
+    /// built on top of it is never invalidated by its own insert.
+    pub fn prepare_insert(&mut self, hash: u64, hash_of: impl Fn(&T) -> u64) -> RawInsertSlot {
+        if let Repr::Array(v) = &mut self.repr {
+            if v.len() < self.array_threshold {
+                return RawInsertSlot(RawInsertSlotInner::Array);
+            }
+            let old = mem::take(v);
+            let mut table = HashedStorage::with_capacity(old.len() + 1);
+            for item in old {
+                let h = hash_of(&item);
+                let index = table.prepare_insert_slot(h);
+                table.insert_at(index, h, item);
+            }
+            self.repr = Repr::Table(table);
+        }
+        match &mut self.repr {
+            Repr::Table(t) => RawInsertSlot(RawInsertSlotInner::Table(t.prepare_insert_slot(hash))),
+            Repr::Array(_) => unreachable!("migrated to Table above"),
+        }
+    }
+
+    /// Inserts `value` into the slot reserved by [`RawTable::prepare_insert`].
+    pub fn insert_in_slot(&mut self, slot: RawInsertSlot, hash: u64, value: T) -> RawLocation {
+        match (&mut self.repr, slot.0) {
+            (Repr::Array(v), RawInsertSlotInner::Array) => {
+                v.push(value);
+                RawLocation(RawLocationInner::Array(v.len() - 1))
+            }
+            (Repr::Table(t), RawInsertSlotInner::Table(index)) => {
+                t.insert_at(index, hash, value);
+                RawLocation(RawLocationInner::Table(index))
+            }
+            _ => unreachable!("RawInsertSlot used against the representation it wasn't made for"),
+        }
+    }
+
+    /// Gets a reference to the value at a location returned by [`RawTable::find`].
+    pub fn get(&self, location: &RawLocation) -> &T {
+        match (&self.repr, &location.0) {
+            (Repr::Array(v), RawLocationInner::Array(i)) => &v[*i],
+            (Repr::Table(t), RawLocationInner::Table(i)) => t.get(*i),
+            _ => unreachable!("RawLocation used against the representation it wasn't made for"),
+        }
+    }
+
+    /// Gets a mutable reference to the value at a location returned by
+    /// [`RawTable::find`].
+    pub fn get_mut(&mut self, location: &RawLocation) -> &mut T {
+        match (&mut self.repr, &location.0) {
+            (Repr::Array(v), RawLocationInner::Array(i)) => &mut v[*i],
+            (Repr::Table(t), RawLocationInner::Table(i)) => t.get_mut(*i),
+            _ => unreachable!("RawLocation used against the representation it wasn't made for"),
+        }
+    }
+
+    /// Removes and returns the value at a location returned by [`RawTable::find`].
+    ///
+    /// This never migrates the table back to the array representation, even if the
+    /// removal drops `len()` back under `array_threshold`.
+    pub fn remove(&mut self, location: RawLocation) -> T {
+        match (&mut self.repr, location.0) {
+            (Repr::Array(v), RawLocationInner::Array(i)) => v.remove(i),
+            (Repr::Table(t), RawLocationInner::Table(i)) => t.remove(i),
+            _ => unreachable!("RawLocation used against the representation it wasn't made for"),
+        }
+    }
+
+    /// An iterator over all the values currently stored.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        let (array, table) = match &self.repr {
+            Repr::Array(v) => (Some(v.iter()), None),
+            Repr::Table(t) => (None, Some(t.slots.iter())),
+        };
+        array
+            .into_iter()
+            .flatten()
+            .chain(table.into_iter().flatten().filter_map(|slot| match slot {
+                Slot::Full(_, item) => Some(item),
+                _ => None,
+            }))
+    }
+}
+
+impl<T, A: Allocator + Clone> Default for RawTable<T, A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hash_of_u64(v: &u64) -> u64 {
+        *v
+    }
+
+    fn insert(table: &mut RawTable<u64>, value: u64) {
+        let slot = table.prepare_insert(value, hash_of_u64);
+        table.insert_in_slot(slot, value, value);
+    }
+
+    #[test]
+    fn stays_array_up_to_threshold() {
+        let mut table: RawTable<u64> = RawTable::with_array_threshold(4);
+        for i in 0..4 {
+            insert(&mut table, i);
+        }
+        assert!(table.is_array());
+        assert_eq!(table.len(), 4);
+    }
+
+    #[test]
+    fn migrates_once_threshold_is_exceeded() {
+        let mut table: RawTable<u64> = RawTable::with_array_threshold(4);
+        for i in 0..4 {
+            insert(&mut table, i);
+        }
+        assert!(table.is_array());
+
+        insert(&mut table, 4);
+        assert!(!table.is_array());
+        assert_eq!(table.len(), 5);
+    }
+
+    #[test]
+    fn lookups_succeed_before_and_after_migration() {
+        let mut table: RawTable<u64> = RawTable::with_array_threshold(4);
+        for i in 0..8 {
+            insert(&mut table, i);
+            // Every key inserted so far must still be findable right after this
+            // insert, whether or not it just triggered the array-to-table migration.
+            for j in 0..=i {
+                let location = table.find(j, |item| *item == j);
+                assert_eq!(location.map(|l| *table.get(&l)), Some(j));
+            }
+        }
+        assert!(table.find(100, |item| *item == 100).is_none());
+    }
+
+    #[test]
+    fn removal_never_migrates_back_to_the_array() {
+        let mut table: RawTable<u64> = RawTable::with_array_threshold(4);
+        for i in 0..8 {
+            insert(&mut table, i);
+        }
+        assert!(!table.is_array());
+
+        for i in 0..8 {
+            let location = table.find(i, |item| *item == i).unwrap();
+            table.remove(location);
+        }
+        assert_eq!(table.len(), 0);
+        assert!(
+            !table.is_array(),
+            "removing every entry must not migrate the table back to the array representation"
+        );
+    }
+
+    #[test]
+    fn tombstones_are_reused_across_many_insert_remove_cycles() {
+        let mut table: RawTable<u64> = RawTable::with_array_threshold(2);
+        for i in 0..4 {
+            insert(&mut table, i);
+        }
+        assert!(!table.is_array());
+
+        for _ in 0..1000 {
+            insert(&mut table, 999);
+            let location = table.find(999, |item| *item == 999).unwrap();
+            table.remove(location);
+        }
+        assert_eq!(table.len(), 4);
+        for i in 0..4 {
+            assert!(table.find(i, |item| *item == i).is_some());
+        }
+
+        // Tombstones left behind by the insert/remove cycles above must be getting
+        // reused rather than endlessly growing the table.
+        match &table.repr {
+            Repr::Table(t) => assert!(
+                t.slots.len() <= 32,
+                "tombstones from repeated insert/remove should be reused, not accumulate: {} slots",
+                t.slots.len()
+            ),
+            Repr::Array(_) => unreachable!("table already migrated above"),
+        }
+    }
+}
@@ -0,0 +1,312 @@
+//! The map-level entry API that [`crate::hash_set`]'s `RustcEntry`-style types build
+//! on top of, named to match the inherent `rustc`-prefixed methods the Rust compiler's
+//! own fork of this crate exposes for `rustc_middle`'s interner-style maps.
+
+use crate::map::make_hash;
+use crate::raw::{Allocator, Global, RawLocation, RawTable};
+use core::hash::{BuildHasher, Hash};
+use core::mem;
+
+/// A view into a single entry in a map, which may either be vacant or occupied.
+///
+/// This `enum` is constructed from the [`rustc_entry`](crate::map::HashMap::rustc_entry)
+/// method on [`HashMap`](crate::HashMap).
+pub enum RustcEntry<'a, K, V, S, A: Allocator + Clone = Global> {
+    /// An occupied entry.
+    Occupied(RustcOccupiedEntry<'a, K, V, A>),
+
+    /// A vacant entry.
+    Vacant(RustcVacantEntry<'a, K, V, S, A>),
+}
+
+/// A view into an occupied entry in a `HashMap`. It is part of the [`RustcEntry`] enum.
+///
+/// Besides the looked-up key/value, this retains the key that was used to look it up
+/// (when available), so that [`RustcOccupiedEntry::replace_key`] can hand back the
+/// stored key while swapping in the one used for the lookup (e.g. to replace one `Rc`
+/// handle with another that compares equal to it).
+pub struct RustcOccupiedEntry<'a, K, V, A: Allocator + Clone = Global> {
+    key: Option<K>,
+    location: RawLocation,
+    table: &'a mut RawTable<(K, V), A>,
+}
+
+impl<'a, K, V, A: Allocator + Clone> RustcOccupiedEntry<'a, K, V, A> {
+    pub(crate) fn new(
+        key: Option<K>,
+        location: RawLocation,
+        table: &'a mut RawTable<(K, V), A>,
+    ) -> Self {
+        RustcOccupiedEntry {
+            key,
+            location,
+            table,
+        }
+    }
+
+    /// Gets a reference to the key in the entry.
+    pub fn key(&self) -> &K {
+        &self.table.get(&self.location).0
+    }
+
+    /// Gets a reference to the value in the entry.
+    pub fn get(&self) -> &V {
+        &self.table.get(&self.location).1
+    }
+
+    /// Gets a mutable reference to the value in the entry.
+    pub fn get_mut(&mut self) -> &mut V {
+        &mut self.table.get_mut(&self.location).1
+    }
+
+    /// Converts the entry into a mutable reference to its value, tied to the original
+    /// map's lifetime rather than the entry's.
+    pub fn into_mut(self) -> &'a mut V {
+        let RustcOccupiedEntry {
+            location, table, ..
+        } = self;
+        &mut table.get_mut(&location).1
+    }
+
+    /// Sets the value of the entry, and returns the entry's old value.
+    pub fn insert(&mut self, value: V) -> V {
+        mem::replace(self.get_mut(), value)
+    }
+
+    /// Takes the value out of the entry, and returns it along with its key.
+    pub fn remove_entry(self) -> (K, V) {
+        let RustcOccupiedEntry {
+            location, table, ..
+        } = self;
+        table.remove(location)
+    }
+
+    /// Takes the value out of the entry, and returns it.
+    pub fn remove(self) -> V {
+        self.remove_entry().1
+    }
+
+    /// Replaces the entry's key with the key that was used to probe for this entry,
+    /// returning the old key. Panics if this entry wasn't constructed from an owned
+    /// probe key (e.g. if it was returned by [`RustcVacantEntry::insert_entry`]).
+    pub fn replace_key(self) -> K {
+        let RustcOccupiedEntry {
+            key,
+            location,
+            table,
+        } = self;
+        let new_key = key.expect("replace_key requires an entry built from an owned probe key");
+        let slot = table.get_mut(&location);
+        mem::replace(&mut slot.0, new_key)
+    }
+}
+
+/// A view into a vacant entry in a `HashMap`. It is part of the [`RustcEntry`] enum.
+pub struct RustcVacantEntry<'a, K, V, S, A: Allocator + Clone = Global> {
+    key: K,
+    hash: u64,
+    hash_builder: &'a S,
+    table: &'a mut RawTable<(K, V), A>,
+}
+
+impl<'a, K: Hash, V, S: BuildHasher, A: Allocator + Clone> RustcVacantEntry<'a, K, V, S, A> {
+    pub(crate) fn new(
+        key: K,
+        hash: u64,
+        hash_builder: &'a S,
+        table: &'a mut RawTable<(K, V), A>,
+    ) -> Self {
+        RustcVacantEntry {
+            key,
+            hash,
+            hash_builder,
+            table,
+        }
+    }
+
+    /// Gets a reference to the key that would be used when inserting through this
+    /// `RustcVacantEntry`.
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Take ownership of the key.
+    pub fn into_key(self) -> K {
+        self.key
+    }
+
+    fn insert_and_locate(self, value: V) -> (&'a mut RawTable<(K, V), A>, RawLocation) {
+        let RustcVacantEntry {
+            key,
+            hash,
+            hash_builder,
+            table,
+        } = self;
+        // `hash` was already computed when this entry was constructed (either from
+        // `rustc_entry`, which hashed `key` once, or `rustc_entry_with_hash`, which
+        // reuses the caller-supplied hash), so inserting here never hashes `key` again.
+        // `prepare_insert` migrates the table from its flat-array representation to a
+        // hashed one if this insertion crosses the configured threshold, rehashing the
+        // array's existing keys through `hash_builder`; since we keep holding `table`
+        // for the rest of this call, that migration can never invalidate the slot it
+        // just reserved for us.
+        let slot = table.prepare_insert(hash, |item| make_hash(hash_builder, &item.0));
+        let location = table.insert_in_slot(slot, hash, (key, value));
+        (table, location)
+    }
+
+    /// Sets the value of the entry with this `RustcVacantEntry`'s key, and returns a
+    /// mutable reference to it.
+    pub fn insert(self, value: V) -> &'a mut V {
+        let (table, location) = self.insert_and_locate(value);
+        &mut table.get_mut(&location).1
+    }
+
+    /// Sets the value of the entry with this `RustcVacantEntry`'s key, and returns a
+    /// `RustcOccupiedEntry`.
+    pub fn insert_entry(self, value: V) -> RustcOccupiedEntry<'a, K, V, A> {
+        let (table, location) = self.insert_and_locate(value);
+        RustcOccupiedEntry::new(None, location, table)
+    }
+}
+
+/// A view into a single entry in a map, which may either be vacant or occupied,
+/// obtained by a borrowed key. This `enum` is constructed from the
+/// [`rustc_entry_ref`](crate::map::HashMap::rustc_entry_ref) method on
+/// [`HashMap`](crate::HashMap).
+pub enum RustcEntryRef<'a, 'b, K, Q: ?Sized, V, S, A: Allocator + Clone = Global> {
+    /// An occupied entry.
+    Occupied(RustcOccupiedEntryRef<'a, K, V, A>),
+
+    /// A vacant entry.
+    Vacant(RustcVacantEntryRef<'a, 'b, K, Q, V, S, A>),
+}
+
+/// A view into an occupied entry in a `HashMap`, obtained by a borrowed key.
+/// It is part of the [`RustcEntryRef`] enum.
+///
+/// Unlike [`RustcOccupiedEntry`], this has no [`replace_key`](RustcOccupiedEntry::replace_key)
+/// method: an occupied lookup through [`rustc_entry_ref`](crate::map::HashMap::rustc_entry_ref)
+/// never has an owned probe key to swap in (only the borrowed one it was passed), so there is
+/// nothing a `replace_key` could sensibly do here.
+pub struct RustcOccupiedEntryRef<'a, K, V, A: Allocator + Clone = Global> {
+    inner: RustcOccupiedEntry<'a, K, V, A>,
+}
+
+impl<'a, K, V, A: Allocator + Clone> RustcOccupiedEntryRef<'a, K, V, A> {
+    pub(crate) fn new(location: RawLocation, table: &'a mut RawTable<(K, V), A>) -> Self {
+        RustcOccupiedEntryRef {
+            inner: RustcOccupiedEntry::new(None, location, table),
+        }
+    }
+
+    /// Gets a reference to the key in the entry.
+    pub fn key(&self) -> &K {
+        self.inner.key()
+    }
+
+    /// Gets a reference to the value in the entry.
+    pub fn get(&self) -> &V {
+        self.inner.get()
+    }
+
+    /// Gets a mutable reference to the value in the entry.
+    pub fn get_mut(&mut self) -> &mut V {
+        self.inner.get_mut()
+    }
+
+    /// Converts the entry into a mutable reference to its value, tied to the original
+    /// map's lifetime rather than the entry's.
+    pub fn into_mut(self) -> &'a mut V {
+        self.inner.into_mut()
+    }
+
+    /// Sets the value of the entry, and returns the entry's old value.
+    pub fn insert(&mut self, value: V) -> V {
+        self.inner.insert(value)
+    }
+
+    /// Takes the value out of the entry, and returns it along with its key.
+    pub fn remove_entry(self) -> (K, V) {
+        self.inner.remove_entry()
+    }
+
+    /// Takes the value out of the entry, and returns it.
+    pub fn remove(self) -> V {
+        self.inner.remove()
+    }
+}
+
+/// A view into a vacant entry in a `HashMap`, obtained by a borrowed key.
+/// It is part of the [`RustcEntryRef`] enum.
+///
+/// The borrowed key is only converted to an owned one (via [`ToOwned::to_owned`]) from
+/// inside [`RustcVacantEntryRef::insert`] or [`RustcVacantEntryRef::insert_entry`], so
+/// an occupied lookup through [`HashMap::rustc_entry_ref`](crate::map::HashMap::rustc_entry_ref)
+/// never pays for that conversion.
+pub struct RustcVacantEntryRef<'a, 'b, K, Q: ?Sized, V, S, A: Allocator + Clone = Global> {
+    key: &'b Q,
+    hash: u64,
+    hash_builder: &'a S,
+    table: &'a mut RawTable<(K, V), A>,
+}
+
+impl<'a, 'b, K: Hash, Q: ?Sized, V, S: BuildHasher, A: Allocator + Clone>
+    RustcVacantEntryRef<'a, 'b, K, Q, V, S, A>
+{
+    pub(crate) fn new(
+        key: &'b Q,
+        hash: u64,
+        hash_builder: &'a S,
+        table: &'a mut RawTable<(K, V), A>,
+    ) -> Self {
+        RustcVacantEntryRef {
+            key,
+            hash,
+            hash_builder,
+            table,
+        }
+    }
+
+    /// Gets a reference to the borrowed key that would be cloned into an owned one
+    /// when inserting through this `RustcVacantEntryRef`.
+    pub fn key(&self) -> &Q {
+        self.key
+    }
+
+    fn insert_and_locate(self, value: V) -> (&'a mut RawTable<(K, V), A>, RawLocation)
+    where
+        Q: alloc::borrow::ToOwned<Owned = K>,
+    {
+        let RustcVacantEntryRef {
+            key,
+            hash,
+            hash_builder,
+            table,
+        } = self;
+        let owned_key = key.to_owned();
+        let slot = table.prepare_insert(hash, |item| make_hash(hash_builder, &item.0));
+        let location = table.insert_in_slot(slot, hash, (owned_key, value));
+        (table, location)
+    }
+
+    /// Sets the value of the entry, cloning the borrowed key into an owned one, and
+    /// returns a mutable reference to the value.
+    pub fn insert(self, value: V) -> &'a mut V
+    where
+        Q: alloc::borrow::ToOwned<Owned = K>,
+    {
+        let (table, location) = self.insert_and_locate(value);
+        &mut table.get_mut(&location).1
+    }
+
+    /// Sets the value of the entry, cloning the borrowed key into an owned one, and
+    /// returns a `RustcOccupiedEntryRef`.
+    pub fn insert_entry(self, value: V) -> RustcOccupiedEntryRef<'a, K, V, A>
+    where
+        Q: alloc::borrow::ToOwned<Owned = K>,
+    {
+        let (table, location) = self.insert_and_locate(value);
+        RustcOccupiedEntryRef::new(location, table)
+    }
+}
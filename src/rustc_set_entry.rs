@@ -2,6 +2,8 @@ use self::RustcEntry::*;
 use crate::raw::{Allocator, Global};
 use crate::rustc_entry;
 use crate::set::HashSet;
+use crate::Equivalent;
+use alloc::borrow::ToOwned;
 use core::fmt::{self, Debug};
 use core::hash::{BuildHasher, Hash};
 
@@ -13,6 +15,14 @@ where
 {
     /// Gets the given value's corresponding entry in the set for in-place manipulation.
     ///
+    /// This delegates straight to the underlying map's entry, so it works the same way
+    /// no matter which of [`RawTable`](crate::raw::RawTable)'s two representations the
+    /// set currently happens to be using: a small linear-scan array below the
+    /// configured threshold, or a fully hashed table above it. A [`RustcVacantEntry`]
+    /// obtained here stays valid across whatever lazy array-to-table migration its own
+    /// `insert` may trigger, since the migration and the insert it's performed for both
+    /// happen while the entry still holds the table borrow.
+    ///
     /// # Examples
     ///
     /// ```
@@ -45,7 +55,7 @@ where
     /// assert!(!singles.contains(&'v') && !dupes.contains(&'v'));
     /// ```
     #[cfg_attr(feature = "inline-more", inline)]
-    pub fn rustc_entry(&mut self, value: T) -> RustcEntry<'_, T, A> {
+    pub fn rustc_entry(&mut self, value: T) -> RustcEntry<'_, T, S, A> {
         match self.map.rustc_entry(value) {
             rustc_entry::RustcEntry::Occupied(entry) => {
                 RustcEntry::Occupied(RustcOccupiedEntry { inner: entry })
@@ -55,6 +65,83 @@ where
             }
         }
     }
+
+    /// Gets the given value's corresponding entry in the set for in-place manipulation,
+    /// using an already computed hash instead of hashing `value` again.
+    ///
+    /// This is useful when the caller already needs the hash for some other purpose
+    /// (e.g. sharding or routing) and wants to avoid paying for it twice.
+    ///
+    /// `hash` must be the same value the set's [`BuildHasher`] would produce for
+    /// `value`. Once the set uses its hashed representation, a mismatched hash silently
+    /// makes the resulting entry unreachable by future [`contains`](HashSet::contains)/
+    /// [`remove`](HashSet::remove) calls, since those hash `value` themselves and will
+    /// probe a different part of the table than where this entry got inserted. While
+    /// the set is still a flat array, `hash` is not consulted, and the array's values
+    /// are rehashed with the set's [`BuildHasher`] when it migrates.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hashbrown::HashSet;
+    /// use std::hash::{BuildHasher, Hash, Hasher};
+    ///
+    /// let mut set: HashSet<&str> = HashSet::new();
+    ///
+    /// let mut hasher = set.hasher().build_hasher();
+    /// "poneyland".hash(&mut hasher);
+    /// let hash = hasher.finish();
+    ///
+    /// set.rustc_entry_with_hash("poneyland", hash).or_insert();
+    /// assert!(set.contains("poneyland"));
+    /// ```
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn rustc_entry_with_hash(&mut self, value: T, hash: u64) -> RustcEntry<'_, T, S, A> {
+        match self.map.rustc_entry_with_hash(value, hash) {
+            rustc_entry::RustcEntry::Occupied(entry) => {
+                RustcEntry::Occupied(RustcOccupiedEntry { inner: entry })
+            }
+            rustc_entry::RustcEntry::Vacant(entry) => {
+                RustcEntry::Vacant(RustcVacantEntry { inner: entry })
+            }
+        }
+    }
+
+    /// Gets the given value's corresponding entry in the set for in-place manipulation,
+    /// without requiring ownership of `value` unless a new entry ends up being inserted.
+    ///
+    /// This avoids an upfront allocation (e.g. for `String`) in the common case where the
+    /// value is already present and nothing new needs to be inserted; the borrowed value
+    /// is only converted to an owned one inside [`RustcVacantEntryRef::insert`] or
+    /// [`RustcVacantEntryRef::insert_entry`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hashbrown::HashSet;
+    /// use hashbrown::hash_set::RustcEntryRef::*;
+    ///
+    /// let mut set: HashSet<String> = HashSet::new();
+    ///
+    /// if let Vacant(entry) = set.rustc_entry_ref("poneyland") {
+    ///     entry.insert();
+    /// }
+    /// assert!(set.contains("poneyland"));
+    /// ```
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn rustc_entry_ref<'b, Q>(&mut self, value: &'b Q) -> RustcEntryRef<'_, 'b, T, Q, S, A>
+    where
+        Q: Equivalent<T> + Hash + ?Sized + ToOwned<Owned = T>,
+    {
+        match self.map.rustc_entry_ref(value) {
+            rustc_entry::RustcEntryRef::Occupied(entry) => {
+                RustcEntryRef::Occupied(RustcOccupiedEntryRef { inner: entry })
+            }
+            rustc_entry::RustcEntryRef::Vacant(entry) => {
+                RustcEntryRef::Vacant(RustcVacantEntryRef { inner: entry })
+            }
+        }
+    }
 }
 
 /// A view into a single entry in a set, which may either be vacant or occupied.
@@ -63,7 +150,7 @@ where
 ///
 /// [`HashSet`]: struct.HashSet.html
 /// [`rustc_entry`]: struct.HashSet.html#method.rustc_entry
-pub enum RustcEntry<'a, T, A = Global>
+pub enum RustcEntry<'a, T, S, A = Global>
 where
     A: Allocator + Clone,
 {
@@ -71,10 +158,10 @@ where
     Occupied(RustcOccupiedEntry<'a, T, A>),
 
     /// A vacant entry.
-    Vacant(RustcVacantEntry<'a, T, A>),
+    Vacant(RustcVacantEntry<'a, T, S, A>),
 }
 
-impl<T: Debug, A: Allocator + Clone> Debug for RustcEntry<'_, T, A> {
+impl<T: Debug + Hash, S: BuildHasher, A: Allocator + Clone> Debug for RustcEntry<'_, T, S, A> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match *self {
             Vacant(ref v) => f.debug_tuple("Entry").field(v).finish(),
@@ -106,20 +193,22 @@ impl<T: Debug, A: Allocator + Clone> Debug for RustcOccupiedEntry<'_, T, A> {
 /// It is part of the [`RustcEntry`] enum.
 ///
 /// [`RustcEntry`]: enum.RustcEntry.html
-pub struct RustcVacantEntry<'a, T, A = Global>
+pub struct RustcVacantEntry<'a, T, S, A = Global>
 where
     A: Allocator + Clone,
 {
-    inner: rustc_entry::RustcVacantEntry<'a, T, (), A>,
+    inner: rustc_entry::RustcVacantEntry<'a, T, (), S, A>,
 }
 
-impl<T: Debug, A: Allocator + Clone> Debug for RustcVacantEntry<'_, T, A> {
+impl<T: Debug + Hash, S: BuildHasher, A: Allocator + Clone> Debug
+    for RustcVacantEntry<'_, T, S, A>
+{
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_tuple("VacantEntry").field(self.get()).finish()
     }
 }
 
-impl<'a, T, A: Allocator + Clone> RustcEntry<'a, T, A> {
+impl<'a, T: Hash, S: BuildHasher, A: Allocator + Clone> RustcEntry<'a, T, S, A> {
     /// Sets the value of the entry, and returns a RustcOccupiedEntry.
     ///
     /// # Examples
@@ -132,7 +221,10 @@ impl<'a, T, A: Allocator + Clone> RustcEntry<'a, T, A> {
     ///
     /// assert_eq!(entry.get(), &"horseyland");
     /// ```
-    pub fn insert(self) -> RustcOccupiedEntry<'a, T, A> {
+    pub fn insert(self) -> RustcOccupiedEntry<'a, T, A>
+    where
+        T: Hash,
+    {
         match self {
             Occupied(entry) => entry,
             Vacant(entry) => entry.insert_entry(),
@@ -249,7 +341,7 @@ impl<'a, T, A: Allocator + Clone> RustcOccupiedEntry<'a, T, A> {
     }
 }
 
-impl<'a, T, A: Allocator + Clone> RustcVacantEntry<'a, T, A> {
+impl<'a, T: Hash, S: BuildHasher, A: Allocator + Clone> RustcVacantEntry<'a, T, S, A> {
     /// Gets a reference to the value that would be used when inserting
     /// through the `RustcVacantEntry`.
     ///
@@ -328,3 +420,204 @@ impl<'a, T, A: Allocator + Clone> RustcVacantEntry<'a, T, A> {
         }
     }
 }
+
+/// A view into a single entry in a set, which may either be vacant or occupied, obtained
+/// by a borrowed key. This `enum` is constructed from the [`rustc_entry_ref`] method on
+/// [`HashSet`].
+///
+/// [`HashSet`]: struct.HashSet.html
+/// [`rustc_entry_ref`]: struct.HashSet.html#method.rustc_entry_ref
+pub enum RustcEntryRef<'a, 'b, T, Q: ?Sized, S, A = Global>
+where
+    A: Allocator + Clone,
+{
+    /// An occupied entry.
+    Occupied(RustcOccupiedEntryRef<'a, T, A>),
+
+    /// A vacant entry.
+    Vacant(RustcVacantEntryRef<'a, 'b, T, Q, S, A>),
+}
+
+impl<T: Debug + Hash, Q: ?Sized + Debug, S: BuildHasher, A: Allocator + Clone> Debug
+    for RustcEntryRef<'_, '_, T, Q, S, A>
+where
+    Q: Equivalent<T> + Hash + ToOwned<Owned = T>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            RustcEntryRef::Vacant(ref v) => f.debug_tuple("EntryRef").field(v).finish(),
+            RustcEntryRef::Occupied(ref o) => f.debug_tuple("EntryRef").field(o).finish(),
+        }
+    }
+}
+
+/// A view into an occupied entry in a `HashSet`, obtained by a borrowed key.
+/// It is part of the [`RustcEntryRef`] enum.
+///
+/// Unlike [`RustcOccupiedEntry`], this has no [`replace`](RustcOccupiedEntry::replace)
+/// method: an occupied lookup through [`rustc_entry_ref`](HashSet::rustc_entry_ref)
+/// never has an owned probe key to swap in (only the borrowed one it was passed), so
+/// there is nothing a `replace` could sensibly do here.
+///
+/// [`RustcEntryRef`]: enum.RustcEntryRef.html
+pub struct RustcOccupiedEntryRef<'a, T, A = Global>
+where
+    A: Allocator + Clone,
+{
+    inner: rustc_entry::RustcOccupiedEntryRef<'a, T, (), A>,
+}
+
+impl<T: Debug, A: Allocator + Clone> Debug for RustcOccupiedEntryRef<'_, T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OccupiedEntryRef")
+            .field("value", self.get())
+            .finish()
+    }
+}
+
+impl<'a, T, A: Allocator + Clone> RustcOccupiedEntryRef<'a, T, A> {
+    /// Gets a reference to the value in the entry.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn get(&self) -> &T {
+        self.inner.key()
+    }
+
+    /// Takes the value out of the entry, and returns it.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn remove(self) -> T {
+        self.inner.remove_entry().0
+    }
+}
+
+/// A view into a vacant entry in a `HashSet`, obtained by a borrowed key.
+/// It is part of the [`RustcEntryRef`] enum.
+///
+/// [`RustcEntryRef`]: enum.RustcEntryRef.html
+pub struct RustcVacantEntryRef<'a, 'b, T, Q: ?Sized, S, A = Global>
+where
+    A: Allocator + Clone,
+{
+    inner: rustc_entry::RustcVacantEntryRef<'a, 'b, T, Q, (), S, A>,
+}
+
+impl<T: Hash, Q: ?Sized + Debug, S: BuildHasher, A: Allocator + Clone> Debug
+    for RustcVacantEntryRef<'_, '_, T, Q, S, A>
+where
+    Q: Equivalent<T> + Hash + ToOwned<Owned = T>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("VacantEntryRef").field(&self.get()).finish()
+    }
+}
+
+impl<'a, 'b, T: Hash, Q, S: BuildHasher, A: Allocator + Clone>
+    RustcVacantEntryRef<'a, 'b, T, Q, S, A>
+where
+    Q: Equivalent<T> + Hash + ?Sized + ToOwned<Owned = T>,
+{
+    /// Gets a reference to the borrowed key that would be cloned into an owned value
+    /// when inserting through this `RustcVacantEntryRef`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hashbrown::HashSet;
+    /// use hashbrown::hash_set::RustcEntryRef;
+    ///
+    /// let mut set: HashSet<String> = HashSet::new();
+    ///
+    /// if let RustcEntryRef::Vacant(entry) = set.rustc_entry_ref("poneyland") {
+    ///     assert_eq!(entry.get(), "poneyland");
+    /// }
+    /// ```
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn get(&self) -> &Q {
+        self.inner.key()
+    }
+
+    /// Sets the value of the entry, cloning the borrowed key into an owned one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hashbrown::HashSet;
+    /// use hashbrown::hash_set::RustcEntryRef;
+    ///
+    /// let mut set: HashSet<String> = HashSet::new();
+    ///
+    /// if let RustcEntryRef::Vacant(o) = set.rustc_entry_ref("poneyland") {
+    ///     o.insert();
+    /// }
+    /// assert!(set.contains("poneyland"));
+    /// ```
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn insert(self) {
+        self.inner.insert(());
+    }
+
+    /// Sets the value of the entry, cloning the borrowed key into an owned one,
+    /// and returns a `RustcOccupiedEntryRef`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hashbrown::HashSet;
+    /// use hashbrown::hash_set::RustcEntryRef;
+    ///
+    /// let mut set: HashSet<String> = HashSet::new();
+    ///
+    /// if let RustcEntryRef::Vacant(v) = set.rustc_entry_ref("poneyland") {
+    ///     let o = v.insert_entry();
+    ///     assert_eq!(o.get(), "poneyland");
+    /// }
+    /// ```
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn insert_entry(self) -> RustcOccupiedEntryRef<'a, T, A> {
+        RustcOccupiedEntryRef {
+            inner: self.inner.insert_entry(()),
+        }
+    }
+}
+
+impl<'a, 'b, T: Hash, Q, S: BuildHasher, A: Allocator + Clone> RustcEntryRef<'a, 'b, T, Q, S, A>
+where
+    Q: Equivalent<T> + Hash + ?Sized + ToOwned<Owned = T>,
+{
+    /// Sets the value of the entry, and returns a RustcOccupiedEntryRef.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hashbrown::HashSet;
+    ///
+    /// let mut set: HashSet<String> = HashSet::new();
+    /// let entry = set.rustc_entry_ref("horseyland").insert();
+    ///
+    /// assert_eq!(entry.get(), "horseyland");
+    /// ```
+    pub fn insert(self) -> RustcOccupiedEntryRef<'a, T, A> {
+        match self {
+            RustcEntryRef::Occupied(entry) => entry,
+            RustcEntryRef::Vacant(entry) => entry.insert_entry(),
+        }
+    }
+
+    /// Ensures a value is in the entry by inserting if it was vacant.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hashbrown::HashSet;
+    ///
+    /// let mut set: HashSet<String> = HashSet::new();
+    ///
+    /// set.rustc_entry_ref("poneyland").or_insert();
+    /// assert!(set.contains("poneyland"));
+    /// ```
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn or_insert(self) {
+        if let RustcEntryRef::Vacant(entry) = self {
+            entry.insert();
+        }
+    }
+}
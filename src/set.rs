@@ -0,0 +1,83 @@
+//! A hash set implemented on top of [`HashMap`](crate::HashMap).
+
+use crate::map::HashMap;
+use crate::raw::{Allocator, Global};
+use crate::Equivalent;
+use core::hash::{BuildHasher, Hash};
+use std::collections::hash_map::RandomState;
+
+/// A hash set, mirroring [`std::collections::HashSet`]'s interface but backed by a
+/// [`HashMap`] whose storage starts out as a flat linear-scan array for small sizes.
+pub struct HashSet<T, S = RandomState, A: Allocator + Clone = Global> {
+    pub(crate) map: HashMap<T, (), S, A>,
+}
+
+impl<T> HashSet<T, RandomState, Global> {
+    /// Creates an empty `HashSet`.
+    pub fn new() -> Self {
+        HashSet {
+            map: HashMap::new(),
+        }
+    }
+}
+
+impl<T> Default for HashSet<T, RandomState, Global> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, S, A: Allocator + Clone> HashSet<T, S, A> {
+    /// Creates an empty `HashSet` which will use `hash_builder` to hash values.
+    pub fn with_hasher(hash_builder: S) -> Self {
+        HashSet {
+            map: HashMap::with_hasher(hash_builder),
+        }
+    }
+
+    /// Creates an empty `HashSet` that stores up to `array_threshold` values in a flat
+    /// linear-scan array before migrating to a hashed representation.
+    pub fn with_hasher_and_array_threshold(hash_builder: S, array_threshold: usize) -> Self {
+        HashSet {
+            map: HashMap::with_hasher_and_array_threshold(hash_builder, array_threshold),
+        }
+    }
+
+    /// The number of elements in the set.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Whether the set contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// A reference to the set's [`BuildHasher`].
+    pub fn hasher(&self) -> &S {
+        self.map.hasher()
+    }
+}
+
+impl<T: Eq + Hash, S: BuildHasher, A: Allocator + Clone> HashSet<T, S, A> {
+    /// Adds a value to the set, returning whether it was newly inserted.
+    pub fn insert(&mut self, value: T) -> bool {
+        self.map.insert(value, ()).is_none()
+    }
+
+    /// Whether the set contains a value.
+    pub fn contains<Q>(&self, value: &Q) -> bool
+    where
+        Q: Equivalent<T> + Hash + ?Sized,
+    {
+        self.map.contains_key(value)
+    }
+
+    /// Removes a value from the set, returning whether it was present.
+    pub fn remove<Q>(&mut self, value: &Q) -> bool
+    where
+        Q: Equivalent<T> + Hash + ?Sized,
+    {
+        self.map.remove(value).is_some()
+    }
+}